@@ -0,0 +1,348 @@
+//! Object and region references into objects within an HDF5 file.
+//!
+//! A reference is a small opaque blob (`H5R_ref_t`) that can be stored as
+//! ordinary data -- in a dataset, an attribute, or as a field of a compound
+//! datatype -- and later resolved back into the object (or selection) it
+//! points at via [`ObjectReference::dereference`].
+
+use std::fmt::{self, Debug};
+use std::mem;
+
+use hdf5_sys::h5i::hid_t;
+use hdf5_sys::h5r::{H5R_ref_t, H5R_type_t, H5Rcreate_object, H5Rget_obj_type3, H5Ropen_object};
+#[cfg(feature = "1.12.0")]
+use hdf5_sys::h5r::{H5Rcreate_attr, H5Rcreate_region, H5Ropen_attr, H5Ropen_region};
+#[cfg(feature = "1.12.0")]
+use hdf5_sys::h5s::{
+    H5Sclose, H5Sget_select_hyperslab_blocklist, H5Sget_select_npoints, H5Sget_select_type,
+    H5Sselect_elements, H5Sselect_hyperslab, H5S_SELECT_SET,
+    H5S_sel_type::{H5S_SEL_HYPERSLABS, H5S_SEL_POINTS},
+};
+
+use hdf5_types::H5Type;
+
+use crate::attribute::Attribute;
+use crate::dataset::Dataset;
+use crate::group::Group;
+use crate::internal_prelude::*;
+#[cfg(feature = "1.12.0")]
+use crate::selection::Selection;
+
+/// A resolved target of a [`StdReference`].
+///
+/// Which variant comes back from [`ObjectReference::dereference`] depends on what
+/// kind of reference was stored -- a whole object, a selection within a
+/// dataset, or a named attribute on an object (the latter two both require
+/// `feature = "1.12.0"`).
+#[derive(Clone, Debug)]
+pub enum ReferencedObject {
+    Group(Group),
+    Dataset(Dataset),
+    #[cfg(feature = "1.12.0")]
+    DatasetRegion { dataset: Dataset, selection: Selection },
+    #[cfg(feature = "1.12.0")]
+    Attribute(Attribute),
+}
+
+/// Trait for creating and resolving [`StdReference`]s against a location
+/// (file, group, or dataset) in an HDF5 file.
+pub trait ObjectReference: Location {
+    /// Creates a reference to the object at `name`, relative to `self`.
+    fn reference(&self, name: &str) -> Result<StdReference> {
+        StdReference::new_object(self.id(), name)
+    }
+
+    /// Creates a reference to the attribute `attr_name` on the object at
+    /// `name`, relative to `self`.
+    ///
+    /// Requires `feature = "1.12.0"`.
+    #[cfg(feature = "1.12.0")]
+    fn attribute_reference(&self, name: &str, attr_name: &str) -> Result<StdReference> {
+        StdReference::new_attr(self.id(), name, attr_name)
+    }
+
+    /// Resolves `reference` into the object (or selection, or attribute) it
+    /// points at.
+    fn dereference(&self, reference: &StdReference) -> Result<ReferencedObject> {
+        dereference(self.id(), reference)
+    }
+}
+
+impl<T: Location> ObjectReference for T {}
+
+/// An opaque, storable reference to an object (or a selection within a
+/// dataset, or an attribute) in an HDF5 file.
+///
+/// `StdReference` wraps the 64-byte `H5R_ref_t` blob introduced in HDF5
+/// 1.10/1.12 and implements [`H5Type`], so it can be used as the element
+/// type of a dataset or attribute, or as a field of a `#[derive(H5Type)]`
+/// struct.
+///
+/// Region and attribute references allocate library-side resources inside
+/// the blob (`H5Rcreate_region`/`H5Rcreate_attr`), which `H5Rdestroy` must
+/// release, so `StdReference` owns its blob and is not `Copy`: it calls
+/// `H5Rdestroy` on drop like any other HDF5 handle wrapper. Use
+/// [`RawReference`] where a `Copy` element type is required, e.g. as the
+/// element type of `VarLenArray` or a fixed-size array field.
+#[repr(transparent)]
+pub struct StdReference(pub(crate) H5R_ref_t);
+
+impl Clone for StdReference {
+    fn clone(&self) -> Self {
+        let mut copy = unsafe { mem::zeroed::<H5R_ref_t>() };
+        let src = &self.0 as *const H5R_ref_t as *mut H5R_ref_t;
+        h5lock!(hdf5_sys::h5r::H5Rcopy(src, &mut copy));
+        Self(copy)
+    }
+}
+
+impl Drop for StdReference {
+    fn drop(&mut self) {
+        let _ = h5lock!(hdf5_sys::h5r::H5Rdestroy(&mut self.0));
+    }
+}
+
+/// The `Copy`, non-owning wire format of a [`StdReference`].
+///
+/// `RawReference` exists only to satisfy the `Copy` bound that array and
+/// compound element types (`VarLenArray<T>`, `[T; N]`) require; it takes on
+/// none of `StdReference`'s cleanup responsibility. A `RawReference` handed
+/// to HDF5 for writing (`Dataset::with_data`, `Attribute::write_scalar`, ...)
+/// is only ever read from by the library, which copies its bytes into the
+/// file and does not retain or allocate against it, so a write-only copy
+/// never needs destroying. A `RawReference` read back from a file, on the
+/// other hand, may have had library-side resources allocated for it and
+/// must be converted with [`RawReference::into_owned`] before it goes out of
+/// scope, so that the resulting [`StdReference`] cleans it up on drop.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct RawReference(H5R_ref_t);
+
+impl RawReference {
+    /// Takes ownership of this reference, returning a [`StdReference`] that
+    /// will call `H5Rdestroy` on drop.
+    pub fn into_owned(self) -> StdReference {
+        StdReference(self.0)
+    }
+}
+
+unsafe impl H5Type for RawReference {
+    fn type_descriptor() -> hdf5_types::TypeDescriptor {
+        hdf5_types::TypeDescriptor::Reference(hdf5_types::ReferenceType::Std)
+    }
+}
+
+impl StdReference {
+    /// Returns a non-owning, `Copy` snapshot of this reference's wire bytes,
+    /// suitable for writing into an array or compound field. See
+    /// [`RawReference`] for the ownership contract.
+    pub fn as_raw(&self) -> RawReference {
+        RawReference(self.0)
+    }
+
+    pub(crate) fn new_object(loc_id: hid_t, name: &str) -> Result<Self> {
+        let name = to_cstring(name)?;
+        let mut reference = unsafe { mem::zeroed::<H5R_ref_t>() };
+        h5call!(H5Rcreate_object(loc_id, name.as_ptr(), H5P_DEFAULT, &mut reference))?;
+        Ok(Self(reference))
+    }
+
+    /// Builds a region reference that points at `selection` within
+    /// `dataset`.
+    #[cfg(feature = "1.12.0")]
+    pub(crate) fn new_region(dataset: &Dataset, selection: &Selection) -> Result<Self> {
+        let parent = dataset.parent()?;
+        let c_name = to_cstring(&dataset.name())?;
+
+        // Bind `space` (instead of taking a bare `.id()`) so the dataspace
+        // stays open across both calls below.
+        let space = dataset.space()?;
+        apply_selection(space.id(), selection)?;
+
+        let mut reference = unsafe { mem::zeroed::<H5R_ref_t>() };
+        h5call!(H5Rcreate_region(
+            parent.id(),
+            c_name.as_ptr(),
+            space.id(),
+            H5P_DEFAULT,
+            &mut reference
+        ))?;
+        Ok(Self(reference))
+    }
+
+    /// Builds a reference to the attribute `attr_name` on the object at
+    /// `name`.
+    #[cfg(feature = "1.12.0")]
+    pub(crate) fn new_attr(loc_id: hid_t, name: &str, attr_name: &str) -> Result<Self> {
+        let c_name = to_cstring(name)?;
+        let c_attr_name = to_cstring(attr_name)?;
+        let mut reference = unsafe { mem::zeroed::<H5R_ref_t>() };
+        h5call!(H5Rcreate_attr(
+            loc_id,
+            c_name.as_ptr(),
+            c_attr_name.as_ptr(),
+            H5P_DEFAULT,
+            &mut reference
+        ))?;
+        Ok(Self(reference))
+    }
+}
+
+/// Applies a [`Selection`] to a dataspace via `H5Sselect_hyperslab` (for a
+/// hyperslab) or `H5Sselect_elements` (for a point list).
+#[cfg(feature = "1.12.0")]
+fn apply_selection(space_id: hid_t, selection: &Selection) -> Result<()> {
+    match selection {
+        Selection::All => {
+            // Nothing to do -- the default selection already covers the
+            // whole dataspace.
+        }
+        Selection::Points(points) => {
+            // `Array2::iter()` already yields individual elements in
+            // row-major order, i.e. exactly the flat coordinate list
+            // `H5Sselect_elements` expects; iterating by `.outer_iter()` row
+            // would double up on one dimension of nesting.
+            let coords: Vec<hdf5_sys::h5::hsize_t> = points.iter().copied().collect();
+            h5call!(H5Sselect_elements(
+                space_id,
+                H5S_SELECT_SET,
+                points.nrows(),
+                coords.as_ptr()
+            ))?;
+        }
+        Selection::Hyperslab(hyperslab) => {
+            let (start, stride, count, block) = hyperslab.to_raw_parts();
+            h5call!(H5Sselect_hyperslab(
+                space_id,
+                H5S_SELECT_SET,
+                start.as_ptr(),
+                stride.as_ptr(),
+                count.as_ptr(),
+                block.as_ptr(),
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads back the selection recorded in a dataspace obtained via
+/// `H5Ropen_region`, expressed in the referenced dataset's own coordinate
+/// space.
+#[cfg(feature = "1.12.0")]
+fn read_back_selection(space_id: hid_t) -> Result<Selection> {
+    let sel_type = h5lock!(H5Sget_select_type(space_id));
+    match sel_type {
+        H5S_SEL_HYPERSLABS => {
+            let nblocks =
+                h5call!(hdf5_sys::h5s::H5Sget_select_hyperslab_nblocks(space_id))? as usize;
+            // `Selection::from_hyperslab_blocklist` rebuilds one block only, so
+            // a hyperslab that HDF5 records as several blocks (a stride larger
+            // than the block) is rejected rather than cut down to its first block.
+            if nblocks != 1 {
+                fail!("region reference hyperslab spans {} blocks; only one is supported", nblocks);
+            }
+            let ndim = h5call!(hdf5_sys::h5s::H5Sget_simple_extent_ndims(space_id))? as usize;
+            let mut blocklist = vec![0 as hdf5_sys::h5::hsize_t; 2 * ndim * nblocks.max(1)];
+            h5call!(H5Sget_select_hyperslab_blocklist(
+                space_id,
+                0,
+                nblocks as _,
+                blocklist.as_mut_ptr()
+            ))?;
+            Ok(Selection::from_hyperslab_blocklist(ndim, &blocklist))
+        }
+        H5S_SEL_POINTS => {
+            let npoints = h5call!(H5Sget_select_npoints(space_id))? as usize;
+            let ndim = h5call!(hdf5_sys::h5s::H5Sget_simple_extent_ndims(space_id))? as usize;
+            let mut coords = vec![0 as hdf5_sys::h5::hsize_t; npoints * ndim];
+            h5call!(hdf5_sys::h5s::H5Sget_select_elem_pointlist(
+                space_id,
+                0,
+                npoints as _,
+                coords.as_mut_ptr()
+            ))?;
+            Ok(Selection::from_pointlist(ndim, &coords))
+        }
+        _ => fail!("unsupported region reference selection type"),
+    }
+}
+
+impl Debug for StdReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StdReference").finish()
+    }
+}
+
+unsafe impl H5Type for StdReference {
+    fn type_descriptor() -> hdf5_types::TypeDescriptor {
+        // A `StdReference` maps to HDF5's own reference datatype (`H5T_REF`),
+        // not a string -- `FixedAscii` would apply null-term/null-pad string
+        // semantics and corrupt the `H5R_ref_t` blob's interior null bytes.
+        hdf5_types::TypeDescriptor::Reference(hdf5_types::ReferenceType::Std)
+    }
+}
+
+impl Dataset {
+    /// Creates a reference to `selection` within this dataset.
+    ///
+    /// Requires `feature = "1.12.0"`. The dataspace used to build the
+    /// reference must have a non-empty selection applied to it, so an empty
+    /// [`Selection`] is rejected; [`Selection::All`] is accepted, since it
+    /// deliberately selects the whole dataset rather than nothing.
+    ///
+    /// A hyperslab made of several separate blocks (a stride larger than the
+    /// block) is stored, but [`ObjectReference::dereference`] returns an error for it.
+    #[cfg(feature = "1.12.0")]
+    pub fn region_reference(&self, selection: Selection) -> Result<StdReference> {
+        if selection.is_empty() {
+            fail!("cannot create a region reference with an empty selection");
+        }
+        StdReference::new_region(self, &selection)
+    }
+}
+
+/// Resolves `reference`, dispatching on the reference kind as reported by
+/// `H5Rget_obj_type3`/`H5Rget_type`.
+///
+/// `_loc_id` is accepted for symmetry with [`ObjectReference::dereference`]
+/// (so `file.dereference(..)` and `group.dereference(..)` both read
+/// naturally), but a `StdReference` is self-contained and the 1.12 `H5Ropen_*`
+/// calls resolve it without needing a location.
+pub(crate) fn dereference(_loc_id: hid_t, reference: &StdReference) -> Result<ReferencedObject> {
+    #[cfg(feature = "1.12.0")]
+    {
+        let ref_type = h5lock!(hdf5_sys::h5r::H5Rget_type(&reference.0 as *const _ as *mut _));
+        if ref_type == H5R_type_t::H5R_DATASET_REGION2 {
+            let dataset_id =
+                h5call!(H5Ropen_object(&reference.0 as *const _ as *mut _, H5P_DEFAULT, H5P_DEFAULT))?;
+            let dataset = Dataset::from_id(dataset_id)?;
+            let space_id =
+                h5call!(H5Ropen_region(&reference.0 as *const _ as *mut _, H5P_DEFAULT, H5P_DEFAULT))?;
+            let selection = read_back_selection(space_id);
+            h5lock!(H5Sclose(space_id));
+            return Ok(ReferencedObject::DatasetRegion { dataset, selection: selection? });
+        }
+        if ref_type == H5R_type_t::H5R_ATTR {
+            let attr_id = h5call!(H5Ropen_attr(
+                &reference.0 as *const _ as *mut _,
+                H5P_DEFAULT,
+                H5P_DEFAULT
+            ))?;
+            return Ok(ReferencedObject::Attribute(Attribute::from_id(attr_id)?));
+        }
+    }
+
+    let mut obj_type = H5O_type_t::H5O_TYPE_UNKNOWN;
+    h5call!(H5Rget_obj_type3(
+        &reference.0 as *const H5R_ref_t as *mut H5R_ref_t,
+        H5P_DEFAULT,
+        &mut obj_type
+    ))?;
+    let obj_id =
+        h5call!(H5Ropen_object(&reference.0 as *const _ as *mut _, H5P_DEFAULT, H5P_DEFAULT))?;
+    match obj_type {
+        H5O_type_t::H5O_TYPE_GROUP => Ok(ReferencedObject::Group(Group::from_id(obj_id)?)),
+        H5O_type_t::H5O_TYPE_DATASET => Ok(ReferencedObject::Dataset(Dataset::from_id(obj_id)?)),
+        _ => fail!("unsupported referenced object type"),
+    }
+}
@@ -0,0 +1,129 @@
+//! Dataspace selections -- hyperslabs and point lists -- used to slice
+//! datasets and to describe the region a [`crate::StdReference`] region
+//! reference points at.
+
+use ndarray::Array2;
+
+use hdf5_sys::h5::hsize_t;
+
+/// The integer type used for dataspace coordinates and extents.
+pub type Ix = hsize_t;
+
+/// A hyperslab expressed as one `(start, stride, count, block)` tuple per
+/// dimension, ready to hand to `H5Sselect_hyperslab`.
+pub type RawHyperslab = Vec<(Ix, Ix, Ix, Ix)>;
+
+/// A hyperslab selection within a dataspace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hyperslab(RawHyperslab);
+
+impl Hyperslab {
+    pub fn new(raw: RawHyperslab) -> Self {
+        Self(raw)
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn raw(&self) -> &RawHyperslab {
+        &self.0
+    }
+
+    /// Splits the per-dimension tuples into the four parallel arrays that
+    /// `H5Sselect_hyperslab` expects.
+    pub fn to_raw_parts(&self) -> (Vec<Ix>, Vec<Ix>, Vec<Ix>, Vec<Ix>) {
+        let mut start = Vec::with_capacity(self.0.len());
+        let mut stride = Vec::with_capacity(self.0.len());
+        let mut count = Vec::with_capacity(self.0.len());
+        let mut block = Vec::with_capacity(self.0.len());
+        for &(s, st, c, b) in &self.0 {
+            start.push(s);
+            stride.push(st);
+            count.push(c);
+            block.push(b);
+        }
+        (start, stride, count, block)
+    }
+}
+
+impl From<std::ops::Range<usize>> for Selection {
+    fn from(range: std::ops::Range<usize>) -> Self {
+        let start = range.start as Ix;
+        let count = (range.end.saturating_sub(range.start)) as Ix;
+        Selection::Hyperslab(Hyperslab::new(vec![(start, 1, count, 1)]))
+    }
+}
+
+/// A selection within a dataspace: either the whole extent, a set of
+/// individual points, or a hyperslab.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selection {
+    /// The entire dataspace, unfiltered.
+    All,
+    /// A list of individual coordinates, one row per point.
+    Points(Array2<Ix>),
+    Hyperslab(Hyperslab),
+}
+
+impl Selection {
+    /// True if the selection is guaranteed to select no elements.
+    ///
+    /// `All` is deliberately never considered empty: it selects the whole
+    /// dataspace, which is a legitimate (if unusual) region to reference.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Selection::All => false,
+            Selection::Points(points) => points.nrows() == 0,
+            Selection::Hyperslab(hyperslab) => {
+                hyperslab.raw().iter().any(|&(_, _, count, block)| count == 0 || block == 0)
+            }
+        }
+    }
+
+    /// Rebuilds a single-block hyperslab selection from the blocklist
+    /// returned by `H5Sget_select_hyperslab_blocklist` (one block's worth of
+    /// `ndim` start coordinates followed by `ndim` end coordinates).
+    pub(crate) fn from_hyperslab_blocklist(ndim: usize, blocklist: &[Ix]) -> Self {
+        let starts = &blocklist[..ndim];
+        let ends = &blocklist[ndim..2 * ndim];
+        let raw = starts.iter().zip(ends.iter()).map(|(&s, &e)| (s, 1, e - s + 1, 1)).collect();
+        Selection::Hyperslab(Hyperslab::new(raw))
+    }
+
+    /// Rebuilds a point selection from the flat, row-major coordinate list
+    /// returned by `H5Sget_select_elem_pointlist`.
+    pub(crate) fn from_pointlist(ndim: usize, coords: &[Ix]) -> Self {
+        let npoints = coords.len() / ndim;
+        let points = Array2::from_shape_vec((npoints, ndim), coords.to_vec())
+            .expect("point list coordinates did not match ndim");
+        Selection::Points(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyperslab_blocklist_roundtrip() {
+        let selection = Selection::from_hyperslab_blocklist(1, &[2, 4]);
+        assert_eq!(selection, Selection::Hyperslab(Hyperslab::new(vec![(2, 1, 3, 1)])));
+        assert!(!selection.is_empty());
+    }
+
+    #[test]
+    fn test_pointlist_roundtrip() {
+        let selection = Selection::from_pointlist(1, &[1, 4, 7]);
+        let points = Array2::from_shape_vec((3, 1), vec![1, 4, 7]).unwrap();
+        assert_eq!(selection, Selection::Points(points));
+        assert!(!selection.is_empty());
+    }
+
+    #[test]
+    fn test_empty_selection() {
+        assert!(!Selection::All.is_empty());
+        assert!(Selection::Points(Array2::from_shape_vec((0, 1), vec![]).unwrap()).is_empty());
+        assert!(Selection::Hyperslab(Hyperslab::new(vec![(0, 1, 0, 1)])).is_empty());
+    }
+}
@@ -4,8 +4,12 @@
 
 mod common;
 
+use ndarray::{arr1, Array2};
+
 use common::util::new_in_memory_file;
-use hdf5::{file, Group, H5Type, ObjectReference, ReferencedObject, StdReference};
+use hdf5::{
+    file, Group, H5Type, ObjectReference, RawReference, ReferencedObject, Selection, StdReference,
+};
 use hdf5_types::VarLenArray;
 
 #[test]
@@ -112,6 +116,79 @@ fn test_reference_errors_on_attribute() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_attribute_references() {
+    let file = new_in_memory_file().unwrap();
+    let ds = file.new_dataset_builder().with_data(&[1, 2, 3]).create("ds").unwrap();
+    ds.new_attr::<i32>().create("attr").unwrap().write_scalar(&42).unwrap();
+
+    let attr_ref = file.attribute_reference("ds", "attr").unwrap();
+    let ds_refs = file.new_dataset_builder().with_data(&[attr_ref]).create("refs").unwrap();
+
+    let read_references = ds_refs.read_1d::<StdReference>().unwrap();
+    match file.dereference(&read_references[0]).unwrap() {
+        ReferencedObject::Attribute(attr) => {
+            assert_eq!(attr.read_scalar::<i32>().unwrap(), 42);
+        }
+        _ => {
+            panic!("Expected an attribute reference");
+        }
+    }
+}
+
+#[test]
+fn test_region_references() {
+    let file = new_in_memory_file().unwrap();
+    let data = arr1(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    let ds = file.new_dataset_builder().with_data(&data).create("ds").unwrap();
+
+    let region_ref = ds.region_reference(Selection::from(2..5)).unwrap();
+
+    let refs = [region_ref];
+    let ds_refs = file.new_dataset_builder().with_data(&refs).create("refs").unwrap();
+    let read_references = ds_refs.read_1d::<StdReference>().unwrap();
+
+    match file.dereference(&read_references[0]).unwrap() {
+        ReferencedObject::DatasetRegion { dataset, selection } => {
+            assert_eq!(dataset.name(), "/ds");
+            // Compare the resolved elements rather than the `Selection` value
+            // itself -- the hyperslab reconstructed from
+            // `H5Sget_select_hyperslab_blocklist` isn't guaranteed to be
+            // structurally identical to the one `Selection::from` built.
+            let values = dataset.read_slice::<i32, _, _>(selection).unwrap();
+            assert_eq!(values.as_slice().unwrap(), &[2, 3, 4]);
+        }
+        _ => {
+            panic!("Expected a dataset region reference");
+        }
+    }
+}
+
+#[test]
+fn test_region_references_points() {
+    let file = new_in_memory_file().unwrap();
+    let data = arr1(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    let ds = file.new_dataset_builder().with_data(&data).create("ds").unwrap();
+
+    let points = Array2::from_shape_vec((3, 1), vec![1, 4, 7]).unwrap();
+    let region_ref = ds.region_reference(Selection::Points(points)).unwrap();
+
+    let refs = [region_ref];
+    let ds_refs = file.new_dataset_builder().with_data(&refs).create("refs").unwrap();
+    let read_references = ds_refs.read_1d::<StdReference>().unwrap();
+
+    match file.dereference(&read_references[0]).unwrap() {
+        ReferencedObject::DatasetRegion { dataset, selection } => {
+            assert_eq!(dataset.name(), "/ds");
+            let values = dataset.read_slice::<i32, _, _>(selection).unwrap();
+            assert_eq!(values.as_slice().unwrap(), &[1, 4, 7]);
+        }
+        _ => {
+            panic!("Expected a dataset region reference");
+        }
+    }
+}
+
 #[test]
 fn test_reference_in_datatype() {
     let dummy_data = [1, 2, 3, 4];
@@ -157,28 +234,28 @@ fn test_reference_in_datatype() {
     }
 }
 
-/* TODO: Should this be possible? Reference not implementing Copy blocks this in a few places.
 #[test]
 fn test_references_in_array_types() {
     let file = new_in_memory_file().unwrap();
     let _ds1 = file.new_dataset_builder().with_data(&[1, 2, 3]).create("ds1").unwrap();
     let _ds2 = file.new_dataset_builder().with_data(&[4, 5, 6]).create("ds2").unwrap();
-    let refs = [file.reference("ds1").unwrap(), file.reference("ds2").unwrap()];
+    let refs =
+        [file.reference("ds1").unwrap().as_raw(), file.reference("ds2").unwrap().as_raw()];
     let refs_array = VarLenArray::from_slice(&refs);
 
-    file.new_attr::<VarLenArray<StdReference>>()
+    file.new_attr::<VarLenArray<RawReference>>()
         .create("var_array")
         .unwrap()
-        .write_scalar(&refs)
+        .write_scalar(&refs_array)
         .unwrap();
 
     let read_array =
-        file.attr("var_array").unwrap().read_scalar::<VarLenArray<StdReference>>().unwrap();
+        file.attr("var_array").unwrap().read_scalar::<VarLenArray<RawReference>>().unwrap();
 
     let read_refs = read_array.as_slice();
 
     assert_eq!(read_refs.len(), 2);
-    match file.dereference(&read_refs[0]).unwrap() {
+    match file.dereference(&read_refs[0].into_owned()).unwrap() {
         ReferencedObject::Dataset(ds) => {
             assert_eq!(ds.name(), "/ds1");
             assert_eq!(ds.read_1d::<i32>().unwrap().as_slice().unwrap(), &[1, 2, 3]);
@@ -187,7 +264,7 @@ fn test_references_in_array_types() {
             panic!("Expected a dataset reference");
         }
     }
-    match file.dereference(&read_refs[1]).unwrap() {
+    match file.dereference(&read_refs[1].into_owned()).unwrap() {
         ReferencedObject::Dataset(ds) => {
             assert_eq!(ds.name(), "/ds2");
             assert_eq!(ds.read_1d::<i32>().unwrap().as_slice().unwrap(), &[4, 5, 6]);
@@ -197,4 +274,42 @@ fn test_references_in_array_types() {
         }
     }
 }
-*/
\ No newline at end of file
+
+#[test]
+fn test_references_in_fixed_array_field() {
+    let file = new_in_memory_file().unwrap();
+    let _ds1 = file.new_dataset_builder().with_data(&[1, 2, 3]).create("ds1").unwrap();
+    let _ds2 = file.new_dataset_builder().with_data(&[4, 5, 6]).create("ds2").unwrap();
+    let refs =
+        [file.reference("ds1").unwrap().as_raw(), file.reference("ds2").unwrap().as_raw()];
+
+    #[derive(H5Type, Clone, Copy)]
+    #[repr(C)]
+    struct RefPair {
+        refs: [RawReference; 2],
+    }
+
+    let ds = file
+        .new_dataset_builder()
+        .with_data(&[RefPair { refs }])
+        .create("ref_pairs")
+        .unwrap();
+
+    let read_data = ds.read_1d::<RefPair>().unwrap();
+    match file.dereference(&read_data[0].refs[0].into_owned()).unwrap() {
+        ReferencedObject::Dataset(ds) => {
+            assert_eq!(ds.name(), "/ds1");
+        }
+        _ => {
+            panic!("Expected a dataset reference");
+        }
+    }
+    match file.dereference(&read_data[0].refs[1].into_owned()).unwrap() {
+        ReferencedObject::Dataset(ds) => {
+            assert_eq!(ds.name(), "/ds2");
+        }
+        _ => {
+            panic!("Expected a dataset reference");
+        }
+    }
+}
\ No newline at end of file